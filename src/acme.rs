@@ -0,0 +1,203 @@
+use crate::{DeSecClient, DeSecError, RecordType, ResourceRecordSet};
+use std::net::ToSocketAddrs;
+use std::time::{Duration, Instant};
+use trust_dns_resolver::config::{NameServerConfig, NameServerConfigGroup, Protocol, ResolverConfig, ResolverOpts};
+use trust_dns_resolver::TokioAsyncResolver;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AcmeError {
+    #[error(transparent)]
+    DeSec(#[from] DeSecError),
+    #[error("Could not determine the authoritative nameservers for {0}")]
+    NoNameservers(String),
+    #[error("DNS-01 challenge did not propagate to all nameservers within the timeout")]
+    PropagationTimeout,
+    #[error("Failed to query nameserver {0}: {1}")]
+    Resolver(String, String),
+}
+
+/// ACME `dns-01` challenge helper, built on top of a [`DeSecClient`].
+///
+/// Manages the `_acme-challenge` TXT rrset for a domain/subname and can poll
+/// the zone's authoritative nameservers directly to confirm the challenge is
+/// observably published before a CA is asked to validate it.
+pub struct AcmeChallenge<'a> {
+    client: &'a DeSecClient,
+}
+
+impl<'a> AcmeChallenge<'a> {
+    pub fn new(client: &'a DeSecClient) -> Self {
+        AcmeChallenge { client }
+    }
+
+    /// Creates or updates the `_acme-challenge[.subname]` TXT rrset with
+    /// `token_digest`, using the domain's `minimum_ttl`.
+    pub async fn set_dns01_challenge(
+        &self,
+        domain: &str,
+        subname: &str,
+        token_digest: &str,
+    ) -> Result<ResourceRecordSet, AcmeError> {
+        let challenge_subname = Self::challenge_subname(subname);
+        let domain_info = self.client.get_domain(domain).await?;
+        let ttl = u64::from(domain_info.minimum_ttl.unwrap_or(3600));
+        let record = format!("\"{}\"", token_digest);
+
+        match self
+            .client
+            .get_rrset(domain, &challenge_subname, &RecordType::TXT)
+            .await
+        {
+            Ok(_) => {
+                let patch = ResourceRecordSet {
+                    records: Some(vec![record]),
+                    ttl: Some(ttl),
+                    ..ResourceRecordSet::default()
+                };
+                Ok(self
+                    .client
+                    .update_rrset(domain, &challenge_subname, &RecordType::TXT, &patch)
+                    .await?)
+            }
+            Err(DeSecError::NotFound) => Ok(self
+                .client
+                .create_rrset(
+                    domain.to_string(),
+                    challenge_subname,
+                    RecordType::TXT,
+                    vec![record],
+                    ttl,
+                )
+                .await?),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Deletes the `_acme-challenge[.subname]` TXT rrset.
+    pub async fn clear_dns01_challenge(&self, domain: &str, subname: &str) -> Result<(), AcmeError> {
+        let challenge_subname = Self::challenge_subname(subname);
+        Ok(self
+            .client
+            .delete_rrset(domain, &challenge_subname, &RecordType::TXT)
+            .await?)
+    }
+
+    /// Polls the zone's authoritative nameservers directly until all of them
+    /// serve `expected` for the challenge TXT record, or `timeout` elapses.
+    pub async fn wait_for_propagation(
+        &self,
+        domain: &str,
+        subname: &str,
+        expected: &str,
+        timeout: Duration,
+    ) -> Result<(), AcmeError> {
+        let challenge_subname = Self::challenge_subname(subname);
+        let fqdn = format!("{}.{}.", challenge_subname, domain);
+
+        let ns_rrset = self.client.get_rrset(domain, "", &RecordType::NS).await?;
+        let nameservers = ns_rrset.records.unwrap_or_default();
+        if nameservers.is_empty() {
+            return Err(AcmeError::NoNameservers(domain.to_string()));
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut backoff = Duration::from_secs(1);
+
+        loop {
+            let mut propagated = true;
+            for nameserver in &nameservers {
+                match Self::query_txt(nameserver, &fqdn).await {
+                    Ok(values) if values.iter().any(|value| value == expected) => {}
+                    _ => {
+                        propagated = false;
+                        break;
+                    }
+                }
+            }
+
+            if propagated {
+                return Ok(());
+            }
+
+            if Instant::now() >= deadline {
+                return Err(AcmeError::PropagationTimeout);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            tokio::time::sleep(backoff.min(remaining)).await;
+            backoff = (backoff * 2).min(Duration::from_secs(30));
+        }
+    }
+
+    async fn query_txt(nameserver: &str, fqdn: &str) -> Result<Vec<String>, AcmeError> {
+        let socket_addr = Self::resolve_nameserver(nameserver).await?;
+
+        let config = ResolverConfig::from_parts(
+            None,
+            vec![],
+            NameServerConfigGroup::from(vec![NameServerConfig {
+                socket_addr,
+                protocol: Protocol::Udp,
+                tls_dns_name: None,
+                trust_negative_responses: false,
+                bind_addr: None,
+            }]),
+        );
+        let resolver = TokioAsyncResolver::tokio(config, ResolverOpts::default());
+
+        let lookup = resolver
+            .txt_lookup(fqdn)
+            .await
+            .map_err(|error| AcmeError::Resolver(nameserver.to_string(), error.to_string()))?;
+
+        Ok(lookup
+            .iter()
+            .map(|record| record.to_string().trim_matches('"').to_string())
+            .collect())
+    }
+
+    /// Resolves `nameserver:53` to a socket address off the async executor,
+    /// since `ToSocketAddrs::to_socket_addrs` is a blocking OS call and this
+    /// runs for each nameserver queried on every `wait_for_propagation`
+    /// iteration.
+    async fn resolve_nameserver(nameserver: &str) -> Result<std::net::SocketAddr, AcmeError> {
+        let target = format!("{}:53", nameserver.trim_end_matches('.'));
+        let resolved = tokio::task::spawn_blocking(move || target.to_socket_addrs())
+            .await
+            .map_err(|error| AcmeError::Resolver(nameserver.to_string(), error.to_string()))?
+            .map_err(|error| AcmeError::Resolver(nameserver.to_string(), error.to_string()))?
+            .next();
+        resolved.ok_or_else(|| {
+            AcmeError::Resolver(
+                nameserver.to_string(),
+                "could not resolve nameserver address".to_string(),
+            )
+        })
+    }
+
+    fn challenge_subname(subname: &str) -> String {
+        if subname.is_empty() {
+            "_acme-challenge".to_string()
+        } else {
+            format!("_acme-challenge.{}", subname)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenge_subname_for_apex() {
+        assert_eq!(AcmeChallenge::challenge_subname(""), "_acme-challenge");
+    }
+
+    #[test]
+    fn challenge_subname_for_subname() {
+        assert_eq!(
+            AcmeChallenge::challenge_subname("www"),
+            "_acme-challenge.www"
+        );
+    }
+}
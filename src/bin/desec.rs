@@ -0,0 +1,364 @@
+use clap::{Parser, Subcommand};
+use desec_client::{DeSecClient, DeSecError, Domain, RecordType, ResourceRecordSet};
+use serde::Serialize;
+use std::str::FromStr;
+
+#[derive(Parser)]
+#[command(name = "desec", about = "Command line client for the deSEC DNS API")]
+struct Cli {
+    /// deSEC API token. Defaults to the DESEC_API_TOKEN environment variable.
+    #[arg(long, env = "DESEC_API_TOKEN", hide_env_values = true)]
+    token: String,
+
+    /// Print output as JSON instead of a human-readable table.
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage domains.
+    #[command(subcommand)]
+    Domain(DomainCommand),
+    /// Manage resource record sets.
+    #[command(subcommand)]
+    Rrset(RrsetCommand),
+    /// Export a domain's zonefile.
+    #[command(subcommand)]
+    Zonefile(ZonefileCommand),
+}
+
+#[derive(Subcommand)]
+enum DomainCommand {
+    /// List all domains.
+    List,
+    /// Get a single domain.
+    Get { name: String },
+    /// Create a domain.
+    Create { name: String },
+    /// Delete a domain.
+    Delete { name: String },
+}
+
+#[derive(Subcommand)]
+enum RrsetCommand {
+    /// List all rrsets of a domain.
+    List { domain: String },
+    /// Get a single rrset.
+    Get {
+        domain: String,
+        subname: String,
+        #[arg(long = "type")]
+        rrset_type: String,
+    },
+    /// Create an rrset.
+    Create {
+        domain: String,
+        subname: String,
+        #[arg(long = "type")]
+        rrset_type: String,
+        #[arg(long)]
+        records: Vec<String>,
+        #[arg(long, default_value_t = 3600)]
+        ttl: u64,
+    },
+    /// Update an rrset's records and/or ttl.
+    Update {
+        domain: String,
+        subname: String,
+        #[arg(long = "type")]
+        rrset_type: String,
+        #[arg(long)]
+        records: Option<Vec<String>>,
+        #[arg(long)]
+        ttl: Option<u64>,
+    },
+    /// Delete an rrset.
+    Delete {
+        domain: String,
+        subname: String,
+        #[arg(long = "type")]
+        rrset_type: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ZonefileCommand {
+    /// Export a domain's zonefile.
+    Export { domain: String },
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let client = DeSecClient::new(cli.token)?;
+
+    match cli.command {
+        Command::Domain(cmd) => run_domain(&client, cmd, cli.json).await?,
+        Command::Rrset(cmd) => run_rrset(&client, cmd, cli.json).await?,
+        Command::Zonefile(cmd) => run_zonefile(&client, cmd, cli.json).await?,
+    }
+
+    Ok(())
+}
+
+async fn run_domain(client: &DeSecClient, cmd: DomainCommand, json: bool) -> Result<(), DeSecError> {
+    match cmd {
+        DomainCommand::List => print_output(&client.get_domains().await?, json),
+        DomainCommand::Get { name } => print_output(&client.get_domain(&name).await?, json),
+        DomainCommand::Create { name } => print_output(&client.create_domain(&name).await?, json),
+        DomainCommand::Delete { name } => {
+            client.delete_domain(&name).await?;
+            println!("deleted domain {}", name);
+        }
+    }
+    Ok(())
+}
+
+async fn run_rrset(client: &DeSecClient, cmd: RrsetCommand, json: bool) -> Result<(), DeSecError> {
+    match cmd {
+        RrsetCommand::List { domain } => print_output(&client.get_rrsets(&domain).await?, json),
+        RrsetCommand::Get {
+            domain,
+            subname,
+            rrset_type,
+        } => {
+            let rrset_type = parse_record_type(&rrset_type);
+            print_output(&client.get_rrset(&domain, &subname, &rrset_type).await?, json)
+        }
+        RrsetCommand::Create {
+            domain,
+            subname,
+            rrset_type,
+            records,
+            ttl,
+        } => {
+            let rrset_type = parse_record_type(&rrset_type);
+            let rrset = client
+                .create_rrset(domain, subname, rrset_type, records, ttl)
+                .await?;
+            print_output(&rrset, json)
+        }
+        RrsetCommand::Update {
+            domain,
+            subname,
+            rrset_type,
+            records,
+            ttl,
+        } => {
+            let rrset_type = parse_record_type(&rrset_type);
+            let patch = ResourceRecordSet {
+                records,
+                ttl,
+                ..ResourceRecordSet::default()
+            };
+            let rrset = client
+                .update_rrset(&domain, &subname, &rrset_type, &patch)
+                .await?;
+            print_output(&rrset, json)
+        }
+        RrsetCommand::Delete {
+            domain,
+            subname,
+            rrset_type,
+        } => {
+            let rrset_type = parse_record_type(&rrset_type);
+            client.delete_rrset(&domain, &subname, &rrset_type).await?;
+            println!("deleted rrset {}.{} {}", subname, domain, rrset_type);
+        }
+    }
+    Ok(())
+}
+
+async fn run_zonefile(client: &DeSecClient, cmd: ZonefileCommand, json: bool) -> Result<(), DeSecError> {
+    match cmd {
+        ZonefileCommand::Export { domain } => {
+            let zonefile = client.get_zonefile(&domain).await?;
+            if json {
+                print_output(&zonefile, json);
+            } else {
+                println!("{}", zonefile);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_record_type(value: &str) -> RecordType {
+    RecordType::from_str(value).unwrap()
+}
+
+fn print_output<T: Serialize + std::fmt::Debug + AsTable>(value: &T, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(value) {
+            Ok(text) => println!("{}", text),
+            Err(_) => println!("{:?}", value),
+        }
+    } else {
+        println!("{}", value.to_table());
+    }
+}
+
+/// Renders a value as a human-readable table for `print_output`'s
+/// non-JSON output.
+trait AsTable {
+    fn to_table(&self) -> String;
+}
+
+impl AsTable for String {
+    fn to_table(&self) -> String {
+        self.clone()
+    }
+}
+
+const DOMAIN_HEADERS: [&str; 4] = ["NAME", "MINIMUM_TTL", "PUBLISHED", "TOUCHED"];
+
+fn domain_row(domain: &Domain) -> Vec<String> {
+    vec![
+        domain.name.clone().unwrap_or_default(),
+        domain
+            .minimum_ttl
+            .map(|ttl| ttl.to_string())
+            .unwrap_or_default(),
+        domain.published.clone().unwrap_or_default(),
+        domain.touched.clone().unwrap_or_default(),
+    ]
+}
+
+impl AsTable for Domain {
+    fn to_table(&self) -> String {
+        render_table(&DOMAIN_HEADERS, &[domain_row(self)])
+    }
+}
+
+impl AsTable for Vec<Domain> {
+    fn to_table(&self) -> String {
+        render_table(&DOMAIN_HEADERS, &self.iter().map(domain_row).collect::<Vec<_>>())
+    }
+}
+
+const RRSET_HEADERS: [&str; 5] = ["SUBNAME", "TYPE", "TTL", "RECORDS", "TOUCHED"];
+
+fn rrset_row(rrset: &ResourceRecordSet) -> Vec<String> {
+    vec![
+        rrset.subname.clone().unwrap_or_default(),
+        rrset
+            .rrset_type
+            .as_ref()
+            .map(|value| value.to_string())
+            .unwrap_or_default(),
+        rrset.ttl.map(|ttl| ttl.to_string()).unwrap_or_default(),
+        rrset.records.clone().unwrap_or_default().join(", "),
+        rrset.touched.clone().unwrap_or_default(),
+    ]
+}
+
+impl AsTable for ResourceRecordSet {
+    fn to_table(&self) -> String {
+        render_table(&RRSET_HEADERS, &[rrset_row(self)])
+    }
+}
+
+impl AsTable for Vec<ResourceRecordSet> {
+    fn to_table(&self) -> String {
+        render_table(&RRSET_HEADERS, &self.iter().map(rrset_row).collect::<Vec<_>>())
+    }
+}
+
+/// Renders `headers` and `rows` as a whitespace-padded table, each column
+/// sized to its widest cell.
+fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut output = String::new();
+    output.push_str(&format_row(headers.iter().map(|h| h.to_string()), &widths));
+    for row in rows {
+        output.push('\n');
+        output.push_str(&format_row(row.iter().cloned(), &widths));
+    }
+    output
+}
+
+fn format_row(cells: impl Iterator<Item = String>, widths: &[usize]) -> String {
+    cells
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect::<Vec<_>>()
+        .join("  ")
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_domain_list() {
+        let cli = Cli::try_parse_from(["desec", "--token", "t", "domain", "list"]).unwrap();
+        assert!(matches!(cli.command, Command::Domain(DomainCommand::List)));
+    }
+
+    #[test]
+    fn parses_rrset_create_with_multiple_records() {
+        let cli = Cli::try_parse_from([
+            "desec",
+            "--token",
+            "t",
+            "rrset",
+            "create",
+            "example.com",
+            "www",
+            "--type",
+            "A",
+            "--records",
+            "1.1.1.1",
+            "--records",
+            "8.8.8.8",
+            "--ttl",
+            "3600",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Rrset(RrsetCommand::Create {
+                domain,
+                subname,
+                rrset_type,
+                records,
+                ttl,
+            }) => {
+                assert_eq!(domain, "example.com");
+                assert_eq!(subname, "www");
+                assert_eq!(rrset_type, "A");
+                assert_eq!(records, vec!["1.1.1.1", "8.8.8.8"]);
+                assert_eq!(ttl, 3600);
+            }
+            _ => panic!("expected Rrset(Create)"),
+        }
+    }
+
+    #[test]
+    fn rejects_missing_token() {
+        let result = Cli::try_parse_from(["desec", "domain", "list"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn renders_domain_table_with_header() {
+        let domain = Domain {
+            name: Some("example.com".to_string()),
+            ..Domain::default()
+        };
+        let table = domain.to_table();
+        assert!(table.starts_with("NAME"));
+        assert!(table.contains("example.com"));
+    }
+}
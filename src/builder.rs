@@ -0,0 +1,80 @@
+use crate::{
+    token_auth_headers, DeSecClient, DeSecError, API_URL, DEFAULT_MAX_RETRIES,
+    DEFAULT_MAX_RETRY_DELAY, DEFAULT_USER_AGENT,
+};
+use std::time::Duration;
+
+/// Builder for [`DeSecClient`], letting callers point the client at a
+/// self-hosted deSEC instance or a mock server and tune its retry behavior.
+pub struct DeSecClientBuilder {
+    token: String,
+    api_url: String,
+    user_agent: String,
+    timeout: Option<Duration>,
+    max_retries: u32,
+    max_retry_delay: Duration,
+}
+
+impl DeSecClientBuilder {
+    pub fn new(token: String) -> Self {
+        DeSecClientBuilder {
+            token,
+            api_url: API_URL.into(),
+            user_agent: DEFAULT_USER_AGENT.into(),
+            timeout: None,
+            max_retries: DEFAULT_MAX_RETRIES,
+            max_retry_delay: DEFAULT_MAX_RETRY_DELAY,
+        }
+    }
+
+    /// Overrides the API base URL, e.g. to point at a self-hosted instance.
+    pub fn api_url(mut self, api_url: String) -> Self {
+        self.api_url = api_url;
+        self
+    }
+
+    /// Overrides the HTTP `User-Agent` sent with every request.
+    pub fn user_agent(mut self, user_agent: String) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// Sets a default timeout applied to every request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Overrides how many times a request is retried after a `429 Too Many
+    /// Requests` response before giving up with [`DeSecError::Throttled`].
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Overrides the ceiling on the delay honored from a `Retry-After`
+    /// header; longer delays are capped to this value.
+    pub fn max_retry_delay(mut self, max_retry_delay: Duration) -> Self {
+        self.max_retry_delay = max_retry_delay;
+        self
+    }
+
+    pub fn build(self) -> Result<DeSecClient, DeSecError> {
+        let headers = token_auth_headers(&self.token)?;
+        let mut client_builder = reqwest::ClientBuilder::new()
+            .user_agent(self.user_agent)
+            .default_headers(headers);
+        if let Some(timeout) = self.timeout {
+            client_builder = client_builder.timeout(timeout);
+        }
+        let client = client_builder
+            .build()
+            .map_err(|error| DeSecError::ClientBuilder(error.to_string()))?;
+        Ok(DeSecClient {
+            client,
+            api_url: self.api_url,
+            max_retries: self.max_retries,
+            max_retry_delay: self.max_retry_delay,
+        })
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{token_auth_headers, DeSecClient, DeSecError, RecordType, DEFAULT_USER_AGENT};
+use reqwest::StatusCode;
+
+static UPDATE_URL: &str = "https://update.dedyn.io";
+static DEFAULT_IPV4_RESOLVER_URL: &str = "https://ipv4.icanhazip.com";
+static DEFAULT_IPV6_RESOLVER_URL: &str = "https://ipv6.icanhazip.com";
+
+/// Outcome of a dynamic DNS update, as reported by deSEC's `update.dedyn.io`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynDnsUpdateResult {
+    /// The record was created or changed.
+    Good,
+    /// The record already matched the requested address.
+    NoChange,
+}
+
+/// A client for deSEC's dynamic DNS update protocol (`update.dedyn.io`).
+///
+/// This talks to a different endpoint than [`DeSecClient`], so it carries its
+/// own `reqwest::Client`, but authenticates with the same per-token scheme.
+///
+/// The deSEC token is only ever attached to requests to `update_url`. IP
+/// resolution hits an arbitrary, unauthenticated third-party "what is my IP"
+/// service, so it goes out over a separate client with no `Authorization`
+/// header, to avoid leaking the token to that service.
+pub struct DynDnsClient {
+    client: reqwest::Client,
+    ip_client: reqwest::Client,
+    update_url: String,
+    ipv4_resolver_url: String,
+    ipv6_resolver_url: String,
+}
+
+impl DynDnsClient {
+    pub fn new(token: String) -> Result<Self, DeSecError> {
+        let headers = token_auth_headers(&token)?;
+        let client = reqwest::ClientBuilder::new()
+            .user_agent(DEFAULT_USER_AGENT)
+            .default_headers(headers)
+            .build()
+            .map_err(|error| DeSecError::ClientBuilder(error.to_string()))?;
+        let ip_client = reqwest::ClientBuilder::new()
+            .user_agent(DEFAULT_USER_AGENT)
+            .build()
+            .map_err(|error| DeSecError::ClientBuilder(error.to_string()))?;
+        Ok(DynDnsClient {
+            client,
+            ip_client,
+            update_url: UPDATE_URL.into(),
+            ipv4_resolver_url: DEFAULT_IPV4_RESOLVER_URL.into(),
+            ipv6_resolver_url: DEFAULT_IPV6_RESOLVER_URL.into(),
+        })
+    }
+
+    /// Overrides the `update.dedyn.io` base URL, e.g. to point at a mock
+    /// server in tests.
+    pub fn with_update_url(mut self, url: String) -> Self {
+        self.update_url = url;
+        self
+    }
+
+    /// Overrides the resolver used to detect the current public IPv4 address.
+    pub fn with_ipv4_resolver_url(mut self, url: String) -> Self {
+        self.ipv4_resolver_url = url;
+        self
+    }
+
+    /// Overrides the resolver used to detect the current public IPv6 address.
+    pub fn with_ipv6_resolver_url(mut self, url: String) -> Self {
+        self.ipv6_resolver_url = url;
+        self
+    }
+
+    /// Detects the current public IPv4 address via the configured resolver.
+    pub async fn current_ipv4(&self) -> Result<String, DeSecError> {
+        self.fetch_ip(&self.ipv4_resolver_url).await
+    }
+
+    /// Detects the current public IPv6 address via the configured resolver.
+    pub async fn current_ipv6(&self) -> Result<String, DeSecError> {
+        self.fetch_ip(&self.ipv6_resolver_url).await
+    }
+
+    async fn fetch_ip(&self, resolver_url: &str) -> Result<String, DeSecError> {
+        let response = self.ip_client.get(resolver_url).send().await?;
+        Ok(response.text().await?.trim().to_string())
+    }
+
+    /// Issues a dynamic update for `hostname`, setting `myipv4`/`myipv6` to
+    /// the given addresses (either may be omitted).
+    pub async fn update(
+        &self,
+        hostname: &str,
+        ipv4: Option<&str>,
+        ipv6: Option<&str>,
+    ) -> Result<DynDnsUpdateResult, DeSecError> {
+        let mut url = format!("{}/?hostname={}", self.update_url, hostname);
+        if let Some(ipv4) = ipv4 {
+            url.push_str(&format!("&myipv4={}", ipv4));
+        }
+        if let Some(ipv6) = ipv6 {
+            url.push_str(&format!("&myipv6={}", ipv6));
+        }
+        let response = self.client.get(url).send().await?;
+        match response.status() {
+            StatusCode::OK => match response.text().await?.trim() {
+                "good" => Ok(DynDnsUpdateResult::Good),
+                "nochange" => Ok(DynDnsUpdateResult::NoChange),
+                other => Err(DeSecError::DynDns(other.to_string())),
+            },
+            _ => Err(DeSecError::DynDns(response.text().await?)),
+        }
+    }
+
+    /// Detects the current public addresses and updates `hostname` with
+    /// whichever of IPv4/IPv6 could be resolved.
+    pub async fn update_auto(&self, hostname: &str) -> Result<DynDnsUpdateResult, DeSecError> {
+        let ipv4 = self.current_ipv4().await.ok();
+        let ipv6 = self.current_ipv6().await.ok();
+        self.update(hostname, ipv4.as_deref(), ipv6.as_deref())
+            .await
+    }
+
+    /// Like [`update_auto`](Self::update_auto), but first compares the
+    /// detected addresses against the `domain`/`subname` rrset via `desec`
+    /// and skips the write entirely if nothing changed.
+    pub async fn update_if_changed(
+        &self,
+        desec: &DeSecClient,
+        hostname: &str,
+        domain: &str,
+        subname: &str,
+    ) -> Result<Option<DynDnsUpdateResult>, DeSecError> {
+        let ipv4 = self.current_ipv4().await.ok();
+        let ipv6 = self.current_ipv6().await.ok();
+
+        let a_changed = Self::records_changed(desec, domain, subname, &RecordType::A, &ipv4).await?;
+        let aaaa_changed =
+            Self::records_changed(desec, domain, subname, &RecordType::AAAA, &ipv6).await?;
+
+        if !a_changed && !aaaa_changed {
+            return Ok(None);
+        }
+
+        self.update(hostname, ipv4.as_deref(), ipv6.as_deref())
+            .await
+            .map(Some)
+    }
+
+    async fn records_changed(
+        desec: &DeSecClient,
+        domain: &str,
+        subname: &str,
+        rrset_type: &RecordType,
+        address: &Option<String>,
+    ) -> Result<bool, DeSecError> {
+        let address = match address {
+            Some(address) => address,
+            None => return Ok(false),
+        };
+        match desec.get_rrset(domain, subname, rrset_type).await {
+            Ok(rrset) => Ok(rrset.records.unwrap_or_default() != vec![address.clone()]),
+            Err(DeSecError::NotFound) => Ok(true),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn client_against(server: &MockServer) -> DynDnsClient {
+        DynDnsClient::new("test-token".to_string())
+            .unwrap()
+            .with_update_url(server.uri())
+    }
+
+    #[tokio::test]
+    async fn current_ipv4_does_not_send_the_desec_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("203.0.113.42"))
+            .mount(&server)
+            .await;
+
+        let client = DynDnsClient::new("test-token".to_string())
+            .unwrap()
+            .with_ipv4_resolver_url(server.uri());
+        let ip = client.current_ipv4().await.unwrap();
+        assert_eq!(ip, "203.0.113.42");
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        let auth_header: wiremock::http::HeaderName = "authorization".parse().unwrap();
+        assert!(!requests[0].headers.contains_key(&auth_header));
+    }
+
+    #[tokio::test]
+    async fn update_good_is_parsed() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("good"))
+            .mount(&server)
+            .await;
+
+        let client = client_against(&server).await;
+        let result = client.update("example.dedyn.io", Some("1.2.3.4"), None).await;
+        assert_eq!(result.unwrap(), DynDnsUpdateResult::Good);
+    }
+
+    #[tokio::test]
+    async fn update_nochange_is_parsed() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("nochange"))
+            .mount(&server)
+            .await;
+
+        let client = client_against(&server).await;
+        let result = client.update("example.dedyn.io", Some("1.2.3.4"), None).await;
+        assert_eq!(result.unwrap(), DynDnsUpdateResult::NoChange);
+    }
+
+    #[tokio::test]
+    async fn update_unexpected_body_is_an_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("badauth"))
+            .mount(&server)
+            .await;
+
+        let client = client_against(&server).await;
+        let result = client.update("example.dedyn.io", Some("1.2.3.4"), None).await;
+        assert!(matches!(result, Err(DeSecError::DynDns(message)) if message == "badauth"));
+    }
+
+    #[tokio::test]
+    async fn update_non_ok_status_is_an_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("badauth"))
+            .mount(&server)
+            .await;
+
+        let client = client_against(&server).await;
+        let result = client.update("example.dedyn.io", Some("1.2.3.4"), None).await;
+        assert!(matches!(result, Err(DeSecError::DynDns(message)) if message == "badauth"));
+    }
+}
@@ -1,7 +1,24 @@
+// `DeSecError::HttpUnexpectedStatus` carries the full `Response` so callers
+// can inspect it; that's a deliberate, pre-existing tradeoff, not something
+// worth boxing just to silence this lint.
+#![allow(clippy::result_large_err)]
+
 use reqwest::{header, Error, Response, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+mod acme;
+mod builder;
+mod dyndns;
+mod record_type;
+mod token;
+pub use acme::{AcmeChallenge, AcmeError};
+pub use builder::DeSecClientBuilder;
+pub use dyndns::{DynDnsClient, DynDnsUpdateResult};
+pub use record_type::RecordType;
+pub use token::{Token, TokenList, TokenOptions, TokenPolicy, TokenPolicyList};
 
-static API_URL: &str = "https://desec.io/api/v1";
+pub(crate) static API_URL: &str = "https://desec.io/api/v1";
 
 #[derive(thiserror::Error, Debug)]
 pub enum DeSecError {
@@ -23,6 +40,10 @@ pub enum DeSecError {
     ClientBuilder(String),
     #[error("Failed to create HTTP client: {0}")]
     Generic(String),
+    #[error("The dynamic DNS update was rejected: {0}")]
+    DynDns(String),
+    #[error("Rate limited by deSEC, retry after {retry_after:?}")]
+    Throttled { retry_after: Duration },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -72,7 +93,7 @@ pub struct ResourceRecordSet {
     pub name: Option<String>,
     #[serde(rename = "type")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub rrset_type: Option<String>,
+    pub rrset_type: Option<RecordType>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub records: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -83,27 +104,34 @@ pub struct ResourceRecordSet {
 
 pub type ResourceRecordSetList = Vec<ResourceRecordSet>;
 
+pub(crate) static DEFAULT_USER_AGENT: &str = "rust-desec-client";
+pub(crate) static DEFAULT_MAX_RETRIES: u32 = 5;
+pub(crate) static DEFAULT_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Builds the `Authorization: Token <token>` header map shared by every
+/// client in this crate that authenticates against a deSEC endpoint.
+pub(crate) fn token_auth_headers(token: &str) -> Result<header::HeaderMap, DeSecError> {
+    let mut headers = header::HeaderMap::new();
+    headers.insert(
+        "Authorization",
+        header::HeaderValue::from_str(format!("Token {}", token).as_str())
+            .map_err(|error| DeSecError::ClientBuilder(error.to_string()))?,
+    );
+    Ok(headers)
+}
+
 pub struct DeSecClient {
-    client: reqwest::Client,
-    api_url: String,
+    pub(crate) client: reqwest::Client,
+    pub(crate) api_url: String,
+    pub(crate) max_retries: u32,
+    pub(crate) max_retry_delay: Duration,
 }
 
 impl DeSecClient {
+    /// Convenience wrapper around [`DeSecClientBuilder`] for the common case
+    /// of talking to the public deSEC API with default settings.
     pub fn new(token: String) -> Result<Self, DeSecError> {
-        let mut headers = header::HeaderMap::new();
-        headers.insert(
-            "Authorization",
-            header::HeaderValue::from_str(format!("Token {}", token.as_str()).as_str()).unwrap(),
-        );
-        let client = reqwest::ClientBuilder::new()
-            .user_agent("rust-desec-client")
-            .default_headers(headers)
-            .build()
-            .map_err(|error| DeSecError::ClientBuilder(error.to_string()))?;
-        Ok(DeSecClient {
-            client,
-            api_url: API_URL.into(),
-        })
+        DeSecClientBuilder::new(token).build()
     }
 
     pub async fn create_domain(&self, domain: &str) -> Result<Domain, DeSecError> {
@@ -117,7 +145,7 @@ impl DeSecClient {
                 StatusCode::FORBIDDEN => Err(DeSecError::DomainLimit),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 
@@ -127,7 +155,7 @@ impl DeSecClient {
                 StatusCode::CREATED => response.json().await.map_err(|error| error.into()),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 
@@ -138,7 +166,7 @@ impl DeSecClient {
                 StatusCode::NOT_FOUND => Err(DeSecError::NotFound),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 
@@ -148,7 +176,7 @@ impl DeSecClient {
                 StatusCode::NO_CONTENT => Ok(()),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 
@@ -162,7 +190,7 @@ impl DeSecClient {
                 StatusCode::NOT_FOUND => Err(DeSecError::NotFound),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 
@@ -170,7 +198,7 @@ impl DeSecClient {
         &self,
         domain: String,
         subname: String,
-        rrset_type: String,
+        rrset_type: RecordType,
         records: Vec<String>,
         ttl: u64,
     ) -> Result<ResourceRecordSet, DeSecError> {
@@ -194,7 +222,7 @@ impl DeSecClient {
                 StatusCode::BAD_REQUEST => Err(DeSecError::BadRequest(response.text().await?)),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 
@@ -216,7 +244,7 @@ impl DeSecClient {
                 StatusCode::BAD_REQUEST => Err(DeSecError::HttpBulk(response.json().await?)),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 
@@ -230,7 +258,7 @@ impl DeSecClient {
                 StatusCode::NOT_FOUND => Err(DeSecError::NotFound),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 
@@ -238,7 +266,7 @@ impl DeSecClient {
         &self,
         domain: &str,
         subname: &str,
-        rrset_type: &str,
+        rrset_type: &RecordType,
     ) -> Result<ResourceRecordSet, DeSecError> {
         match self
             .get(format!("/domains/{}/rrsets/{}/{}/", domain, subname, rrset_type).as_str())
@@ -249,7 +277,7 @@ impl DeSecClient {
                 StatusCode::NOT_FOUND => Err(DeSecError::NotFound),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 
@@ -257,7 +285,7 @@ impl DeSecClient {
         &self,
         domain: &str,
         subname: &str,
-        rrset_type: &str,
+        rrset_type: &RecordType,
         patch: &ResourceRecordSet,
     ) -> Result<ResourceRecordSet, DeSecError> {
         match self
@@ -273,7 +301,7 @@ impl DeSecClient {
                 StatusCode::BAD_REQUEST => Err(DeSecError::HttpBulk(response.json().await?)),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 
@@ -295,7 +323,7 @@ impl DeSecClient {
                 StatusCode::BAD_REQUEST => Err(DeSecError::HttpBulk(response.json().await?)),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
         }
     }
 
@@ -303,7 +331,7 @@ impl DeSecClient {
         &self,
         domain: &str,
         subname: &str,
-        rrset_type: &str,
+        rrset_type: &RecordType,
     ) -> Result<(), DeSecError> {
         match self
             .delete(format!("/domains/{}/rrsets/{}/{}/", domain, subname, rrset_type).as_str())
@@ -313,39 +341,275 @@ impl DeSecClient {
                 StatusCode::NO_CONTENT => Ok(()),
                 _ => Err(DeSecError::HttpUnexpectedStatus(response)),
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub async fn get_tokens(&self) -> Result<TokenList, DeSecError> {
+        match self.get("/auth/tokens/").await {
+            Ok(response) => match response.status() {
+                StatusCode::OK => response.json().await.map_err(|error| error.into()),
+                _ => Err(DeSecError::HttpUnexpectedStatus(response)),
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    pub async fn create_token(
+        &self,
+        name: String,
+        options: TokenOptions,
+    ) -> Result<Token, DeSecError> {
+        let token = Token {
+            name: Some(name),
+            perm_manage_tokens: options.perm_manage_tokens,
+            allowed_subnets: options.allowed_subnets,
+            max_age: options.max_age,
+            max_unused_period: options.max_unused_period,
+            ..Token::default()
+        };
+        match self
+            .post(
+                "/auth/tokens/",
+                serde_json::to_string(&token).map_err(|err| DeSecError::Parser(err.to_string()))?,
+            )
+            .await
+        {
+            Ok(response) => match response.status() {
+                StatusCode::CREATED => response.json().await.map_err(|error| error.into()),
+                StatusCode::BAD_REQUEST => Err(DeSecError::BadRequest(response.text().await?)),
+                _ => Err(DeSecError::HttpUnexpectedStatus(response)),
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    pub async fn delete_token(&self, id: &str) -> Result<(), DeSecError> {
+        match self.delete(format!("/auth/tokens/{}/", id).as_str()).await {
+            Ok(response) => match response.status() {
+                StatusCode::NO_CONTENT => Ok(()),
+                StatusCode::NOT_FOUND => Err(DeSecError::NotFound),
+                _ => Err(DeSecError::HttpUnexpectedStatus(response)),
+            },
+            Err(error) => Err(error),
         }
     }
 
-    async fn get(&self, endpoint: &str) -> Result<Response, Error> {
-        self.client
-            .get(format!("{}{}", self.api_url, endpoint))
-            .send()
+    pub async fn get_token_policies(&self, token_id: &str) -> Result<TokenPolicyList, DeSecError> {
+        match self
+            .get(format!("/auth/tokens/{}/policies/rrsets/", token_id).as_str())
             .await
+        {
+            Ok(response) => match response.status() {
+                StatusCode::OK => response.json().await.map_err(|error| error.into()),
+                StatusCode::NOT_FOUND => Err(DeSecError::NotFound),
+                _ => Err(DeSecError::HttpUnexpectedStatus(response)),
+            },
+            Err(error) => Err(error),
+        }
     }
 
-    async fn post(&self, endpoint: &str, body: String) -> Result<Response, Error> {
-        self.client
-            .post(format!("{}{}", self.api_url, endpoint).as_str())
-            .header("Content-Type", "application/json")
-            .body(body.to_string())
-            .send()
+    pub async fn create_token_policy(
+        &self,
+        token_id: &str,
+        policy: &TokenPolicy,
+    ) -> Result<TokenPolicy, DeSecError> {
+        match self
+            .post(
+                format!("/auth/tokens/{}/policies/rrsets/", token_id).as_str(),
+                serde_json::to_string(policy).map_err(|err| DeSecError::Parser(err.to_string()))?,
+            )
             .await
+        {
+            Ok(response) => match response.status() {
+                StatusCode::CREATED => response.json().await.map_err(|error| error.into()),
+                StatusCode::BAD_REQUEST => Err(DeSecError::BadRequest(response.text().await?)),
+                _ => Err(DeSecError::HttpUnexpectedStatus(response)),
+            },
+            Err(error) => Err(error),
+        }
     }
 
-    async fn patch(&self, endpoint: &str, body: String) -> Result<Response, Error> {
-        self.client
-            .patch(format!("{}{}", self.api_url, endpoint).as_str())
-            .header("Content-Type", "application/json")
-            .body(body)
-            .send()
+    pub async fn delete_token_policy(
+        &self,
+        token_id: &str,
+        policy_id: &str,
+    ) -> Result<(), DeSecError> {
+        match self
+            .delete(format!("/auth/tokens/{}/policies/rrsets/{}/", token_id, policy_id).as_str())
             .await
+        {
+            Ok(response) => match response.status() {
+                StatusCode::NO_CONTENT => Ok(()),
+                StatusCode::NOT_FOUND => Err(DeSecError::NotFound),
+                _ => Err(DeSecError::HttpUnexpectedStatus(response)),
+            },
+            Err(error) => Err(error),
+        }
     }
 
-    async fn delete(&self, endpoint: &str) -> Result<Response, Error> {
-        self.client
-            .delete(format!("{}{}", self.api_url, endpoint).as_str())
-            .send()
+    async fn get(&self, endpoint: &str) -> Result<Response, DeSecError> {
+        let url = format!("{}{}", self.api_url, endpoint);
+        self.send_with_retry(|| self.client.get(&url).send()).await
+    }
+
+    async fn post(&self, endpoint: &str, body: String) -> Result<Response, DeSecError> {
+        let url = format!("{}{}", self.api_url, endpoint);
+        self.send_with_retry(|| {
+            self.client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .body(body.clone())
+                .send()
+        })
+        .await
+    }
+
+    async fn patch(&self, endpoint: &str, body: String) -> Result<Response, DeSecError> {
+        let url = format!("{}{}", self.api_url, endpoint);
+        self.send_with_retry(|| {
+            self.client
+                .patch(&url)
+                .header("Content-Type", "application/json")
+                .body(body.clone())
+                .send()
+        })
+        .await
+    }
+
+    async fn delete(&self, endpoint: &str) -> Result<Response, DeSecError> {
+        let url = format!("{}{}", self.api_url, endpoint);
+        self.send_with_retry(|| self.client.delete(&url).send())
             .await
     }
+
+    /// Sends a request built by `build_request`, retrying on `429 Too Many
+    /// Requests` by honoring the `Retry-After` header, up to `max_retries`
+    /// retries after the first attempt, each delay capped at `max_retry_delay`.
+    async fn send_with_retry<F, Fut>(&self, build_request: F) -> Result<Response, DeSecError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<Response, Error>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let response = build_request().await?;
+            if response.status() != StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+
+            let retry_after =
+                Self::parse_retry_after(response.headers().get(header::RETRY_AFTER))
+                    .min(self.max_retry_delay);
+
+            attempt += 1;
+            if attempt > self.max_retries {
+                return Err(DeSecError::Throttled { retry_after });
+            }
+
+            tokio::time::sleep(retry_after).await;
+        }
+    }
+
+    fn parse_retry_after(header_value: Option<&header::HeaderValue>) -> Duration {
+        let default_delay = Duration::from_secs(1);
+        let value = match header_value.and_then(|value| value.to_str().ok()) {
+            Some(value) => value,
+            None => return default_delay,
+        };
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Duration::from_secs(seconds);
+        }
+        httpdate::parse_http_date(value)
+            .ok()
+            .and_then(|date| date.duration_since(std::time::SystemTime::now()).ok())
+            .unwrap_or(default_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn parse_retry_after_accepts_delta_seconds() {
+        let header = header::HeaderValue::from_static("2");
+        assert_eq!(
+            DeSecClient::parse_retry_after(Some(&header)),
+            Duration::from_secs(2)
+        );
+    }
+
+    #[test]
+    fn parse_retry_after_accepts_http_date() {
+        let future = std::time::SystemTime::now() + Duration::from_secs(30);
+        let formatted = httpdate::fmt_http_date(future);
+        let header = header::HeaderValue::from_str(&formatted).unwrap();
+        let delay = DeSecClient::parse_retry_after(Some(&header));
+        // fmt_http_date truncates to whole seconds, so allow a little slack.
+        assert!(delay.as_secs() >= 28 && delay.as_secs() <= 30);
+    }
+
+    #[test]
+    fn parse_retry_after_defaults_on_missing_header() {
+        assert_eq!(
+            DeSecClient::parse_retry_after(None),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn parse_retry_after_defaults_on_garbage_header() {
+        let header = header::HeaderValue::from_static("not-a-delay");
+        assert_eq!(
+            DeSecClient::parse_retry_after(Some(&header)),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_after_429_then_succeeds() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/domains/example.com/rrsets/"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/domains/example.com/rrsets/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+
+        let client = DeSecClientBuilder::new("test-token".to_string())
+            .api_url(server.uri())
+            .max_retries(2)
+            .build()
+            .unwrap();
+
+        let rrsets = client.get_rrsets("example.com").await.unwrap();
+        assert!(rrsets.is_empty());
+    }
+
+    #[tokio::test]
+    async fn gives_up_as_throttled_after_max_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/domains/example.com/rrsets/"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .mount(&server)
+            .await;
+
+        let client = DeSecClientBuilder::new("test-token".to_string())
+            .api_url(server.uri())
+            .max_retries(1)
+            .build()
+            .unwrap();
+
+        let result = client.get_rrsets("example.com").await;
+        assert!(matches!(result, Err(DeSecError::Throttled { .. })));
+    }
 }
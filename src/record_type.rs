@@ -0,0 +1,89 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A DNS resource record type, as accepted by the deSEC rrset `type` field.
+///
+/// Unrecognized types are preserved verbatim via `Other` so that the client
+/// keeps working against record types added to the API after this enum was
+/// written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordType {
+    A,
+    AAAA,
+    TXT,
+    MX,
+    NS,
+    CNAME,
+    SOA,
+    SRV,
+    CAA,
+    TLSA,
+    DS,
+    DNSKEY,
+    PTR,
+    Other(String),
+}
+
+impl fmt::Display for RecordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RecordType::A => "A",
+            RecordType::AAAA => "AAAA",
+            RecordType::TXT => "TXT",
+            RecordType::MX => "MX",
+            RecordType::NS => "NS",
+            RecordType::CNAME => "CNAME",
+            RecordType::SOA => "SOA",
+            RecordType::SRV => "SRV",
+            RecordType::CAA => "CAA",
+            RecordType::TLSA => "TLSA",
+            RecordType::DS => "DS",
+            RecordType::DNSKEY => "DNSKEY",
+            RecordType::PTR => "PTR",
+            RecordType::Other(value) => value,
+        })
+    }
+}
+
+impl FromStr for RecordType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value {
+            "A" => RecordType::A,
+            "AAAA" => RecordType::AAAA,
+            "TXT" => RecordType::TXT,
+            "MX" => RecordType::MX,
+            "NS" => RecordType::NS,
+            "CNAME" => RecordType::CNAME,
+            "SOA" => RecordType::SOA,
+            "SRV" => RecordType::SRV,
+            "CAA" => RecordType::CAA,
+            "TLSA" => RecordType::TLSA,
+            "DS" => RecordType::DS,
+            "DNSKEY" => RecordType::DNSKEY,
+            "PTR" => RecordType::PTR,
+            other => RecordType::Other(other.to_string()),
+        })
+    }
+}
+
+impl Serialize for RecordType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for RecordType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        Ok(RecordType::from_str(&value).unwrap())
+    }
+}
@@ -0,0 +1,107 @@
+use crate::RecordType;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Token {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<String>,
+    /// The plaintext token value, present only in the response to
+    /// [`DeSecClient::create_token`](crate::DeSecClient::create_token).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub perm_manage_tokens: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_subnets: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_unused_period: Option<String>,
+}
+
+pub type TokenList = Vec<Token>;
+
+/// Optional settings for [`DeSecClient::create_token`](crate::DeSecClient::create_token).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TokenOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub perm_manage_tokens: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_subnets: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_unused_period: Option<String>,
+}
+
+/// A restriction on what a [`Token`] may be used for, scoped to a
+/// domain/subname/rrset type, with a default-deny/allow flag.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TokenPolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subname: Option<String>,
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rrset_type: Option<RecordType>,
+    pub write: bool,
+}
+
+pub type TokenPolicyList = Vec<TokenPolicy>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_policy_omits_type_key() {
+        let policy = TokenPolicy {
+            domain: Some("example.com".to_string()),
+            rrset_type: None,
+            write: false,
+            ..TokenPolicy::default()
+        };
+
+        let json = serde_json::to_value(&policy).unwrap();
+        assert!(!json.as_object().unwrap().contains_key("type"));
+    }
+
+    #[test]
+    fn scoped_policy_includes_type_key() {
+        let policy = TokenPolicy {
+            domain: Some("example.com".to_string()),
+            subname: Some("_acme-challenge".to_string()),
+            rrset_type: Some(RecordType::TXT),
+            write: true,
+            ..TokenPolicy::default()
+        };
+
+        let json = serde_json::to_value(&policy).unwrap();
+        assert_eq!(json["type"], "TXT");
+        assert_eq!(json["write"], true);
+    }
+
+    #[test]
+    fn token_round_trips_through_json() {
+        let token = Token {
+            name: Some("ci-deploy".to_string()),
+            perm_manage_tokens: Some(false),
+            allowed_subnets: Some(vec!["127.0.0.1/32".to_string()]),
+            ..Token::default()
+        };
+
+        let json = serde_json::to_string(&token).unwrap();
+        let parsed: Token = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.name, token.name);
+        assert_eq!(parsed.allowed_subnets, token.allowed_subnets);
+    }
+}
@@ -1,6 +1,6 @@
 use desec_client::DeSecClient;
 use desec_client::DeSecError;
-use tokio::time::{sleep, Duration};
+use desec_client::RecordType;
 
 fn read_apikey() -> Option<String> {
     std::env::var("DESEC_API_TOKEN").ok()
@@ -33,7 +33,6 @@ fn setup() -> (DeSecClient, Domain, Subname) {
 async fn create_delete_domain() {
     let (client, _, _) = setup();
     let domain_create = read_domain_create().unwrap();
-    let sleep_duration = Duration::from_millis(1000);
 
     // check if domain exists
     match client.get_domain(&domain_create).await {
@@ -48,13 +47,9 @@ async fn create_delete_domain() {
         Err(_) => panic!("Could not check if domain already exists"),
     };
 
-    sleep(sleep_duration).await;
-
     let result = client.create_domain(&domain_create).await;
     assert!(result.is_ok(), "Failed to create domain");
 
-    sleep(sleep_duration).await;
-
     // We successfully created the domain, now lets clean up
     let res = client.delete_domain(&domain_create).await;
     assert!(res.is_ok(), "Failed to delete previously created domain");
@@ -64,9 +59,8 @@ async fn create_delete_domain() {
 async fn create_update_delete_rrset() {
     let (client, domain, subname) = setup();
 
-    let rrset_type = String::from("A");
+    let rrset_type = RecordType::A;
     let records = vec![String::from("8.8.8.8")];
-    let sleep_duration = Duration::from_millis(1000);
 
     // check if rrset exists
     match client.get_rrset(&domain, &subname, &rrset_type).await {
@@ -81,8 +75,6 @@ async fn create_update_delete_rrset() {
         Err(_) => panic!("Could not check if rrset already exists"),
     };
 
-    sleep(sleep_duration).await;
-
     // create new rrset
     let result = client
         .create_rrset(
@@ -95,14 +87,10 @@ async fn create_update_delete_rrset() {
         .await;
     assert!(result.is_ok(), "Failed to create rrset");
 
-    sleep(sleep_duration).await;
-
     // get new created rrset
     let result = client.get_rrset(&domain, &subname, &rrset_type).await;
     assert!(result.is_ok(), "Failed to get new rrset");
 
-    sleep(sleep_duration).await;
-
     // update new rrset
     let mut rrset = result.unwrap();
     rrset.ttl = Some(3650);
@@ -112,8 +100,6 @@ async fn create_update_delete_rrset() {
         .await;
     assert!(rrset.is_ok(), "Failed to update rrset");
 
-    sleep(sleep_duration).await;
-
     let result = client.delete_rrset(&domain, &subname, &rrset_type).await;
     assert!(result.is_ok(), "Failed to delete rrset");
 }
@@ -125,12 +111,11 @@ async fn get_rrsets() {
     assert!(rrsets.is_ok(), "Failed to get rrsets");
     let rrsets = rrsets.unwrap();
     // at least one NS record should be in the result
-    let rrset_type = "NS";
-    let empty_string = String::new();
+    let rrset_type = RecordType::NS;
     assert!(
         rrsets
             .iter()
-            .filter(|rrset| rrset.rrset_type.as_ref().unwrap_or(&empty_string) == rrset_type)
+            .filter(|rrset| rrset.rrset_type.as_ref() == Some(&rrset_type))
             .count()
             > 0
     );